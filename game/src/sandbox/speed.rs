@@ -8,14 +8,83 @@ use geom::{Duration, Polygon, Time};
 use sim::AlertLocation;
 use widgetry::{
     hotkey, Btn, Choice, Color, EventCtx, GeomBatch, GfxCtx, HorizontalAlignment, Key, Line,
-    Outcome, Panel, PersistentSplit, RewriteColor, Text, VerticalAlignment, Widget,
+    Outcome, Panel, PersistentSplit, RewriteColor, ScreenPt, ScreenRectangle, Text,
+    VerticalAlignment, Widget,
 };
 
+// Ramp from one multiplier to another over this many update frames, rather than snapping
+// instantly. See SpeedControls::update_current_multiplier.
+const RAMP_FRAMES: usize = 15;
+
 pub struct SpeedControls {
     pub panel: Panel,
 
     paused: bool,
     setting: SpeedSetting,
+
+    // The multiplier actually applied to the sim right now, eased towards setting.multiplier()
+    // instead of snapping to it.
+    current_multiplier: f64,
+    ramp_start_multiplier: f64,
+    ramp_frames_elapsed: usize,
+
+    // True if the last step we asked the sim to take consumed its entire frame budget without
+    // finishing, meaning we're CPU-bound instead of keeping up in real-time.
+    running_behind: bool,
+
+    breakpoints: Vec<RegisteredBreakpoint>,
+    // Shown when the player clicks "Breakpoints" in the main panel; lets them inspect, toggle,
+    // and remove registered breakpoints without blocking the rest of the UI.
+    breakpoints_panel: Option<Panel>,
+    // The most recent person named by an engine alert. There's no person-picker in this panel,
+    // so this is the only way the breakpoints panel can offer a PersonArrives breakpoint: let
+    // the player register one against whoever they were just told about.
+    last_alerted_person: Option<sim::PersonID>,
+}
+
+// A condition that, once true, should auto-pause the sim. Most of these piggyback on the
+// engine's existing alert feed (see the alert-handling code in SpeedControls::check_breakpoints)
+// instead of requiring a new way to query the sim's state.
+#[derive(Clone)]
+enum Breakpoint {
+    // Pause once the sim clock reaches this time.
+    AtTime(Time),
+    // Pause on any engine-generated alert at all (gridlock, turn-conflict cycles, etc).
+    AnyAlert,
+    // Pause on an alert whose message contains this substring.
+    AlertContains(String),
+    // Pause on an alert located at this person (for example, when they arrive somewhere).
+    PersonArrives(sim::PersonID),
+}
+
+impl Breakpoint {
+    fn describe(&self) -> String {
+        match self {
+            Breakpoint::AtTime(t) => format!("At {}", t.ampm_tostring()),
+            Breakpoint::AnyAlert => "On any alert".to_string(),
+            Breakpoint::AlertContains(needle) => format!("On alert containing \"{}\"", needle),
+            Breakpoint::PersonArrives(p) => format!("When {:?} arrives", p),
+        }
+    }
+
+    // Does this alert (location, message) trip this breakpoint? AtTime breakpoints are checked
+    // separately, against the sim clock, not against alerts.
+    fn matches_alert(&self, loc: &AlertLocation, msg: &str) -> bool {
+        match self {
+            Breakpoint::AtTime(_) => false,
+            Breakpoint::AnyAlert => true,
+            Breakpoint::AlertContains(needle) => msg.contains(needle.as_str()),
+            Breakpoint::PersonArrives(person) => {
+                matches!(loc, AlertLocation::Person(p) if p == person)
+            }
+        }
+    }
+}
+
+#[derive(Clone)]
+struct RegisteredBreakpoint {
+    breakpoint: Breakpoint,
+    enabled: bool,
 }
 
 #[derive(Clone, Copy, PartialEq, PartialOrd)]
@@ -30,8 +99,25 @@ enum SpeedSetting {
     Fastest,
 }
 
+impl SpeedSetting {
+    fn multiplier(self) -> f64 {
+        match self {
+            SpeedSetting::Realtime => 1.0,
+            SpeedSetting::Fast => 5.0,
+            SpeedSetting::Faster => 30.0,
+            SpeedSetting::Fastest => 3600.0,
+        }
+    }
+}
+
 impl SpeedControls {
-    fn make_panel(ctx: &mut EventCtx, app: &App, paused: bool, setting: SpeedSetting) -> Panel {
+    fn make_panel(
+        ctx: &mut EventCtx,
+        app: &App,
+        paused: bool,
+        setting: SpeedSetting,
+        running_behind: bool,
+    ) -> Panel {
         let mut row = Vec::new();
         row.push(
             if paused {
@@ -69,7 +155,13 @@ impl SpeedControls {
 
                     GeomBatch::load_svg(ctx.prerender, "system/assets/speed/triangle.svg")
                         .color(if setting >= s {
-                            RewriteColor::NoOp
+                            if running_behind {
+                                // Dim the active triangle to show we're not actually keeping up
+                                // with the requested speed.
+                                RewriteColor::ChangeAll(Color::WHITE.alpha(0.5))
+                            } else {
+                                RewriteColor::NoOp
+                            }
                         } else {
                             RewriteColor::ChangeAll(Color::WHITE.alpha(0.2))
                         })
@@ -113,6 +205,10 @@ impl SpeedControls {
                     .build(ctx, "reset to midnight", hotkey(Key::X))
                     .container()
                     .padding(9),
+                Btn::text_fg("Breakpoints")
+                    .build(ctx, "breakpoints", None)
+                    .container()
+                    .padding(9),
             ])
             .bg(app.cs.section_bg),
         );
@@ -126,14 +222,181 @@ impl SpeedControls {
     }
 
     pub fn new(ctx: &mut EventCtx, app: &App) -> SpeedControls {
-        let panel = SpeedControls::make_panel(ctx, app, false, SpeedSetting::Realtime);
+        let panel = SpeedControls::make_panel(ctx, app, false, SpeedSetting::Realtime, false);
         SpeedControls {
             panel,
             paused: false,
             setting: SpeedSetting::Realtime,
+            current_multiplier: SpeedSetting::Realtime.multiplier(),
+            ramp_start_multiplier: SpeedSetting::Realtime.multiplier(),
+            ramp_frames_elapsed: RAMP_FRAMES,
+            running_behind: false,
+            breakpoints: Vec::new(),
+            breakpoints_panel: None,
+            last_alerted_person: None,
         }
     }
 
+    pub fn add_breakpoint_at_time(&mut self, ctx: &mut EventCtx, t: Time) {
+        self.breakpoints.push(RegisteredBreakpoint {
+            breakpoint: Breakpoint::AtTime(t),
+            enabled: true,
+        });
+        self.sync_breakpoints_panel(ctx);
+    }
+
+    pub fn add_breakpoint_on_any_alert(&mut self, ctx: &mut EventCtx) {
+        self.breakpoints.push(RegisteredBreakpoint {
+            breakpoint: Breakpoint::AnyAlert,
+            enabled: true,
+        });
+        self.sync_breakpoints_panel(ctx);
+    }
+
+    pub fn add_breakpoint_on_alert_containing(&mut self, ctx: &mut EventCtx, needle: String) {
+        self.breakpoints.push(RegisteredBreakpoint {
+            breakpoint: Breakpoint::AlertContains(needle),
+            enabled: true,
+        });
+        self.sync_breakpoints_panel(ctx);
+    }
+
+    pub fn add_breakpoint_on_person_arrival(&mut self, ctx: &mut EventCtx, person: sim::PersonID) {
+        self.breakpoints.push(RegisteredBreakpoint {
+            breakpoint: Breakpoint::PersonArrives(person),
+            enabled: true,
+        });
+        self.sync_breakpoints_panel(ctx);
+    }
+
+    // Bounds-checked so a stale index from a breakpoints panel that got out of sync (it
+    // shouldn't, now that every mutator calls sync_breakpoints_panel) can't panic.
+    fn remove_breakpoint(&mut self, ctx: &mut EventCtx, idx: usize) {
+        if idx < self.breakpoints.len() {
+            self.breakpoints.remove(idx);
+            self.sync_breakpoints_panel(ctx);
+        }
+    }
+
+    fn toggle_breakpoint_enabled(&mut self, ctx: &mut EventCtx, idx: usize) {
+        if let Some(rb) = self.breakpoints.get_mut(idx) {
+            rb.enabled = !rb.enabled;
+            self.sync_breakpoints_panel(ctx);
+        }
+    }
+
+    // Rebuilds the breakpoints panel to reflect the current list, if it's currently open.
+    // Anything that adds, removes, or toggles a breakpoint -- including auto-removal when an
+    // AtTime breakpoint trips -- calls this afterwards so the panel never shows stale indices.
+    fn sync_breakpoints_panel(&mut self, ctx: &mut EventCtx) {
+        if self.breakpoints_panel.is_some() {
+            self.breakpoints_panel = Some(SpeedControls::make_breakpoints_panel(
+                ctx,
+                &self.breakpoints,
+                self.last_alerted_person.clone(),
+            ));
+        }
+    }
+
+    fn make_breakpoints_panel(
+        ctx: &mut EventCtx,
+        breakpoints: &[RegisteredBreakpoint],
+        last_alerted_person: Option<sim::PersonID>,
+    ) -> Panel {
+        let mut col = vec![Line("Breakpoints").small_heading().draw(ctx)];
+        if breakpoints.is_empty() {
+            col.push(Line("None registered yet").secondary().draw(ctx));
+        }
+        for (idx, rb) in breakpoints.iter().enumerate() {
+            col.push(
+                Widget::custom_row(vec![
+                    Btn::text_fg(if rb.enabled { "[x]" } else { "[ ]" }).build(
+                        ctx,
+                        format!("toggle breakpoint {}", idx),
+                        None,
+                    ),
+                    Line(rb.breakpoint.describe()).draw(ctx).margin_left(8),
+                    Btn::text_fg("X")
+                        .build(ctx, format!("remove breakpoint {}", idx), None)
+                        .margin_left(8),
+                ])
+                .evenly_spaced(),
+            );
+        }
+        col.push(
+            Widget::custom_row(vec![
+                Btn::text_fg("pause on any alert").build(ctx, "add breakpoint: any alert", None),
+                Btn::text_fg("pause on gridlock")
+                    .build(ctx, "add breakpoint: gridlock", None)
+                    .margin_left(8),
+                Btn::text_fg("pause in 1 hour")
+                    .build(ctx, "add breakpoint: in 1 hour", None)
+                    .margin_left(8),
+            ])
+            .margin_above(8),
+        );
+        // There's no person-picker in this panel, so the only person we can offer to target is
+        // whoever the engine most recently named in an alert.
+        if let Some(person) = last_alerted_person {
+            col.push(
+                Btn::text_fg(format!("pause when {:?} arrives again", person))
+                    .build(ctx, "add breakpoint: last alerted person", None)
+                    .margin_above(8),
+            );
+        }
+        col.push(Btn::text_fg("Close").build(ctx, "close breakpoints", None).margin_above(8));
+
+        Panel::new(Widget::col(col))
+            .aligned(HorizontalAlignment::Right, VerticalAlignment::BottomAboveOSD)
+            .build(ctx)
+    }
+
+    // Checks AtTime breakpoints against the sim clock. Alert-based breakpoints (AnyAlert,
+    // AlertContains, PersonArrives) are checked against the alerts in
+    // SpeedControls::check_breakpoints instead, since that's the only place the engine surfaces
+    // that state.
+    fn check_time_breakpoints(&mut self, ctx: &mut EventCtx, app: &mut App) -> Option<Transition> {
+        let now = app.primary.sim.time();
+        let tripped = self.breakpoints.iter().position(|rb| {
+            rb.enabled && matches!(rb.breakpoint, Breakpoint::AtTime(t) if now >= t)
+        })?;
+        let desc = self.breakpoints[tripped].breakpoint.describe();
+        self.breakpoints.remove(tripped);
+        self.sync_breakpoints_panel(ctx);
+        self.pause(ctx, app);
+        Some(Transition::Push(PopupMsg::new(
+            ctx,
+            "Breakpoint hit",
+            vec![desc],
+        )))
+    }
+
+    // Begin easing current_multiplier towards the new setting's multiplier, instead of jumping
+    // straight to it.
+    fn change_setting(&mut self, ctx: &mut EventCtx, app: &App, setting: SpeedSetting) {
+        self.setting = setting;
+        self.ramp_start_multiplier = self.current_multiplier;
+        self.ramp_frames_elapsed = 0;
+        self.panel =
+            SpeedControls::make_panel(ctx, app, self.paused, self.setting, self.running_behind);
+    }
+
+    // Once frames_elapsed catches up to RAMP_FRAMES, snap to the target exactly.
+    fn update_current_multiplier(&mut self) {
+        if self.ramp_frames_elapsed >= RAMP_FRAMES {
+            self.current_multiplier = self.setting.multiplier();
+            return;
+        }
+        self.ramp_frames_elapsed += 1;
+        let target = self.setting.multiplier();
+        self.current_multiplier = ease_multiplier(
+            self.ramp_start_multiplier,
+            target,
+            self.ramp_frames_elapsed,
+            RAMP_FRAMES,
+        );
+    }
+
     pub fn event(
         &mut self,
         ctx: &mut EventCtx,
@@ -143,28 +406,30 @@ impl SpeedControls {
         match self.panel.event(ctx) {
             Outcome::Clicked(x) => match x.as_ref() {
                 "real-time speed" => {
-                    self.setting = SpeedSetting::Realtime;
-                    self.panel = SpeedControls::make_panel(ctx, app, self.paused, self.setting);
+                    self.change_setting(ctx, app, SpeedSetting::Realtime);
                     return None;
                 }
                 "5x speed" => {
-                    self.setting = SpeedSetting::Fast;
-                    self.panel = SpeedControls::make_panel(ctx, app, self.paused, self.setting);
+                    self.change_setting(ctx, app, SpeedSetting::Fast);
                     return None;
                 }
                 "30x speed" => {
-                    self.setting = SpeedSetting::Faster;
-                    self.panel = SpeedControls::make_panel(ctx, app, self.paused, self.setting);
+                    self.change_setting(ctx, app, SpeedSetting::Faster);
                     return None;
                 }
                 "3600x speed" => {
-                    self.setting = SpeedSetting::Fastest;
-                    self.panel = SpeedControls::make_panel(ctx, app, self.paused, self.setting);
+                    self.change_setting(ctx, app, SpeedSetting::Fastest);
                     return None;
                 }
                 "play" => {
                     self.paused = false;
-                    self.panel = SpeedControls::make_panel(ctx, app, self.paused, self.setting);
+                    self.panel = SpeedControls::make_panel(
+                        ctx,
+                        app,
+                        self.paused,
+                        self.setting,
+                        self.running_behind,
+                    );
                     return None;
                 }
                 "pause" => {
@@ -192,6 +457,14 @@ impl SpeedControls {
                         maybe_mode.cloned(),
                     )));
                 }
+                "breakpoints" => {
+                    self.breakpoints_panel = Some(SpeedControls::make_breakpoints_panel(
+                        ctx,
+                        &self.breakpoints,
+                        self.last_alerted_person.clone(),
+                    ));
+                    return None;
+                }
                 "step forwards" => {
                     let dt = self.panel.persistent_split_value("step forwards");
                     if dt == Duration::seconds(0.1) {
@@ -212,24 +485,54 @@ impl SpeedControls {
             },
             _ => {}
         }
+
+        if let Some(ref mut panel) = self.breakpoints_panel {
+            if let Outcome::Clicked(x) = panel.event(ctx) {
+                match x.as_ref() {
+                    "close breakpoints" => {
+                        self.breakpoints_panel = None;
+                    }
+                    "add breakpoint: any alert" => {
+                        self.add_breakpoint_on_any_alert(ctx);
+                    }
+                    "add breakpoint: gridlock" => {
+                        self.add_breakpoint_on_alert_containing(
+                            ctx,
+                            "Turn conflict cycle".to_string(),
+                        );
+                    }
+                    "add breakpoint: in 1 hour" => {
+                        let t = app.primary.sim.time() + Duration::hours(1);
+                        self.add_breakpoint_at_time(ctx, t);
+                    }
+                    "add breakpoint: last alerted person" => {
+                        if let Some(person) = self.last_alerted_person.clone() {
+                            self.add_breakpoint_on_person_arrival(ctx, person);
+                        }
+                    }
+                    x => {
+                        if let Some(idx) = x.strip_prefix("toggle breakpoint ") {
+                            self.toggle_breakpoint_enabled(ctx, idx.parse().unwrap());
+                        } else if let Some(idx) = x.strip_prefix("remove breakpoint ") {
+                            self.remove_breakpoint(ctx, idx.parse().unwrap());
+                        } else {
+                            unreachable!()
+                        }
+                    }
+                }
+                return None;
+            }
+        }
+
         // Just kind of constantly scrape this
         app.opts.time_increment = self.panel.persistent_split_value("step forwards");
 
         if ctx.input.key_pressed(Key::LeftArrow) {
             match self.setting {
                 SpeedSetting::Realtime => self.pause(ctx, app),
-                SpeedSetting::Fast => {
-                    self.setting = SpeedSetting::Realtime;
-                    self.panel = SpeedControls::make_panel(ctx, app, self.paused, self.setting);
-                }
-                SpeedSetting::Faster => {
-                    self.setting = SpeedSetting::Fast;
-                    self.panel = SpeedControls::make_panel(ctx, app, self.paused, self.setting);
-                }
-                SpeedSetting::Fastest => {
-                    self.setting = SpeedSetting::Faster;
-                    self.panel = SpeedControls::make_panel(ctx, app, self.paused, self.setting);
-                }
+                SpeedSetting::Fast => self.change_setting(ctx, app, SpeedSetting::Realtime),
+                SpeedSetting::Faster => self.change_setting(ctx, app, SpeedSetting::Fast),
+                SpeedSetting::Fastest => self.change_setting(ctx, app, SpeedSetting::Faster),
             }
         }
         if ctx.input.key_pressed(Key::RightArrow) {
@@ -237,20 +540,19 @@ impl SpeedControls {
                 SpeedSetting::Realtime => {
                     if self.paused {
                         self.paused = false;
-                        self.panel = SpeedControls::make_panel(ctx, app, self.paused, self.setting);
+                        self.panel = SpeedControls::make_panel(
+                            ctx,
+                            app,
+                            self.paused,
+                            self.setting,
+                            self.running_behind,
+                        );
                     } else {
-                        self.setting = SpeedSetting::Fast;
-                        self.panel = SpeedControls::make_panel(ctx, app, self.paused, self.setting);
+                        self.change_setting(ctx, app, SpeedSetting::Fast);
                     }
                 }
-                SpeedSetting::Fast => {
-                    self.setting = SpeedSetting::Faster;
-                    self.panel = SpeedControls::make_panel(ctx, app, self.paused, self.setting);
-                }
-                SpeedSetting::Faster => {
-                    self.setting = SpeedSetting::Fastest;
-                    self.panel = SpeedControls::make_panel(ctx, app, self.paused, self.setting);
-                }
+                SpeedSetting::Fast => self.change_setting(ctx, app, SpeedSetting::Faster),
+                SpeedSetting::Faster => self.change_setting(ctx, app, SpeedSetting::Fastest),
                 SpeedSetting::Fastest => {}
             }
         }
@@ -258,62 +560,125 @@ impl SpeedControls {
         if !self.paused {
             if let Some(real_dt) = ctx.input.nonblocking_is_update_event() {
                 ctx.input.use_update_event();
-                let multiplier = match self.setting {
-                    SpeedSetting::Realtime => 1.0,
-                    SpeedSetting::Fast => 5.0,
-                    SpeedSetting::Faster => 30.0,
-                    SpeedSetting::Fastest => 3600.0,
+                self.update_current_multiplier();
+                let dt = self.current_multiplier * real_dt;
+                // TODO This budgets off of how long the *last* frame took, which is a
+                // backward-looking stand-in for the real frame deadline, not the deadline
+                // itself. Once widgetry plumbs along the remaining wall-clock time until the
+                // next frame is actually due, budget against that instead. Floor this estimate
+                // so one freak tiny real_dt doesn't starve the sim of a usable budget.
+                let real_budget = Duration::seconds(real_dt);
+                let min_budget = Duration::seconds(1.0 / 30.0);
+                let frame_budget = if real_budget > min_budget {
+                    real_budget
+                } else {
+                    min_budget
                 };
-                let dt = multiplier * real_dt;
-                // TODO This should match the update frequency in widgetry. Plumb along the deadline
-                // or frequency to here.
-                app.primary.sim.time_limited_step(
+                let ran_out_of_time = app.primary.sim.time_limited_step(
                     &app.primary.map,
                     dt,
-                    Duration::seconds(0.033),
+                    frame_budget,
                     &mut app.primary.sim_cb,
                 );
                 app.recalculate_current_selection(ctx);
+
+                // Trust time_limited_step's own signal for whether it hit the budget, rather
+                // than inferring it from how far the sim clock moved. The latter drifts
+                // false-positive once current_multiplier is continuously ramping, since even a
+                // fully-caught-up step rarely lands exactly on the requested dt.
+                let running_behind = ran_out_of_time;
+                if running_behind != self.running_behind {
+                    self.running_behind = running_behind;
+                    self.panel = SpeedControls::make_panel(
+                        ctx,
+                        app,
+                        self.paused,
+                        self.setting,
+                        self.running_behind,
+                    );
+                }
             }
         }
 
-        // TODO Need to do this anywhere that steps the sim, like TimeWarpScreen.
+        if let Some(transition) = self.check_breakpoints(ctx, app) {
+            return Some(transition);
+        }
+
+        None
+    }
+
+    // Checks both AtTime and alert-based breakpoints against the current sim state, pausing and
+    // returning a popup if one trips. Call this after any code steps the sim, not just from this
+    // panel's own event loop.
+    //
+    // TODO TimeWarpScreen steps the sim too (used by seek_to, "jump to specific time", and
+    // "step forwards") and does not call this yet, so warping through time -- including via
+    // TimePanel's own scrubber -- currently blows past registered breakpoints instead of
+    // stopping on them. Wire TimeWarpScreen's step loop up to this exact helper.
+    pub fn check_breakpoints(&mut self, ctx: &mut EventCtx, app: &mut App) -> Option<Transition> {
+        if let Some(transition) = self.check_time_breakpoints(ctx, app) {
+            return Some(transition);
+        }
+
         let alerts = app.primary.sim.clear_alerts();
         if !alerts.is_empty() {
-            let popup = PopupMsg::new(
-                ctx,
-                "Alerts",
-                alerts.iter().map(|(_, _, msg)| msg).collect(),
-            );
-            let maybe_id = match alerts[0].1 {
-                AlertLocation::Nil => None,
-                AlertLocation::Intersection(i) => Some(ID::Intersection(i)),
-                // TODO Open info panel and warp to them
-                AlertLocation::Person(_) => None,
-                AlertLocation::Building(b) => Some(ID::Building(b)),
-            };
-            // TODO Can filter for particular alerts places like this:
-            /*if !alerts[0].2.contains("Turn conflict cycle") {
-                return None;
-            }*/
-            /*if maybe_id != Some(ID::Building(map_model::BuildingID(91))) {
-                return None;
-            }*/
-            self.pause(ctx, app);
-            if let Some(id) = maybe_id {
-                // Just go to the first one, but print all messages
-                return Some(Transition::Multi(vec![
-                    Transition::Push(popup),
-                    Transition::Push(Warping::new(
-                        ctx,
-                        id.canonical_point(&app.primary).unwrap(),
-                        Some(10.0),
-                        None,
-                        &mut app.primary,
-                    )),
-                ]));
+            // Remember the last person an alert named, so the breakpoints panel has someone to
+            // offer a PersonArrives breakpoint against.
+            for (_, loc, _) in &alerts {
+                if let AlertLocation::Person(p) = loc {
+                    self.last_alerted_person = Some(p.clone());
+                }
+            }
+
+            // If the player has any enabled alert-matching breakpoints, only react to alerts
+            // that trip one of them. Otherwise, fall back to the old behavior of pausing on
+            // every engine-generated alert.
+            let alert_breakpoints: Vec<&Breakpoint> = self
+                .breakpoints
+                .iter()
+                .filter(|rb| rb.enabled)
+                .map(|rb| &rb.breakpoint)
+                .filter(|bp| !matches!(bp, Breakpoint::AtTime(_)))
+                .collect();
+            let relevant: Vec<_> = if alert_breakpoints.is_empty() {
+                alerts.iter().collect()
             } else {
-                return Some(Transition::Push(popup));
+                alerts
+                    .iter()
+                    .filter(|(_, loc, msg)| {
+                        alert_breakpoints.iter().any(|bp| bp.matches_alert(loc, msg))
+                    })
+                    .collect()
+            };
+            if !relevant.is_empty() {
+                let popup = PopupMsg::new(
+                    ctx,
+                    "Alerts",
+                    relevant.iter().map(|(_, _, msg)| msg).collect(),
+                );
+                let maybe_id = match relevant[0].1 {
+                    AlertLocation::Nil => None,
+                    AlertLocation::Intersection(i) => Some(ID::Intersection(i)),
+                    // TODO Open info panel and warp to them
+                    AlertLocation::Person(_) => None,
+                    AlertLocation::Building(b) => Some(ID::Building(b)),
+                };
+                self.pause(ctx, app);
+                if let Some(id) = maybe_id {
+                    // Just go to the first one, but print all messages
+                    return Some(Transition::Multi(vec![
+                        Transition::Push(popup),
+                        Transition::Push(Warping::new(
+                            ctx,
+                            id.canonical_point(&app.primary).unwrap(),
+                            Some(10.0),
+                            None,
+                            &mut app.primary,
+                        )),
+                    ]));
+                } else {
+                    return Some(Transition::Push(popup));
+                }
             }
         }
 
@@ -322,20 +687,28 @@ impl SpeedControls {
 
     pub fn draw(&self, g: &mut GfxCtx) {
         self.panel.draw(g);
+        if let Some(ref panel) = self.breakpoints_panel {
+            panel.draw(g);
+        }
     }
 
     pub fn pause(&mut self, ctx: &mut EventCtx, app: &App) {
         if !self.paused {
             self.paused = true;
-            self.panel = SpeedControls::make_panel(ctx, app, self.paused, self.setting);
+            self.panel = SpeedControls::make_panel(
+                ctx,
+                app,
+                self.paused,
+                self.setting,
+                self.running_behind,
+            );
         }
     }
 
     pub fn resume_realtime(&mut self, ctx: &mut EventCtx, app: &App) {
         if self.paused || self.setting != SpeedSetting::Realtime {
             self.paused = false;
-            self.setting = SpeedSetting::Realtime;
-            self.panel = SpeedControls::make_panel(ctx, app, self.paused, self.setting);
+            self.change_setting(ctx, app, SpeedSetting::Realtime);
         }
     }
 
@@ -344,31 +717,62 @@ impl SpeedControls {
     }
 }
 
+// Manually tuned to match the drawn bar in TimePanel::new_with_preview.
+const TIME_BAR_WIDTH: f64 = 300.0;
+const TIME_BAR_HEIGHT: f64 = 15.0;
+
+// The finest tick spacing (up to an hour) is chosen so the visible window spans at most this many
+// steps between ticks.
+const MAX_TICKS: f64 = 12.0;
+// Each scroll notch zooms in/out by this factor.
+const ZOOM_FACTOR: f64 = 0.8;
+
 pub struct TimePanel {
     time: Time,
     pub panel: Panel,
+    // True while the player is pressing and dragging on the bar, previewing a time they haven't
+    // committed to yet.
+    scrubbing: bool,
+    // The sub-range of the day currently visible on the ruler. Defaults to the whole day.
+    window: (Time, Time),
+    // While the player is holding the right mouse button down on the bar, this is where they
+    // started dragging from and what the window looked like then.
+    pan_anchor: Option<(ScreenPt, (Time, Time))>,
 }
 
 impl TimePanel {
     pub fn new(ctx: &mut EventCtx, app: &App) -> TimePanel {
+        let window = (Time::START_OF_DAY, app.primary.sim.get_end_of_day());
+        TimePanel::new_with_preview(ctx, app, app.primary.sim.time(), window)
+    }
+
+    fn new_with_preview(
+        ctx: &mut EventCtx,
+        app: &App,
+        preview_time: Time,
+        window: (Time, Time),
+    ) -> TimePanel {
+        let end_of_day = app.primary.sim.get_end_of_day();
         TimePanel {
             time: app.primary.sim.time(),
+            scrubbing: false,
+            window,
+            pan_anchor: None,
             panel: Panel::new(Widget::col(vec![
-                Text::from(Line(app.primary.sim.time().ampm_tostring()).big_monospaced())
+                Text::from(Line(preview_time.ampm_tostring()).big_monospaced())
                     .draw(ctx)
                     .centered_horiz(),
                 {
                     let mut batch = GeomBatch::new();
-                    // This is manually tuned
-                    let width = 300.0;
-                    let height = 15.0;
-                    // Just clamp if we simulate past the expected end
-                    let percent = app
-                        .primary
-                        .sim
-                        .time()
-                        .to_percent(app.primary.sim.get_end_of_day())
-                        .min(1.0);
+                    let width = TIME_BAR_WIDTH;
+                    let height = TIME_BAR_HEIGHT;
+                    // Where does preview_time fall in the visible window? Clamp to the edges if
+                    // it's currently scrolled off-screen.
+                    let percent = percent_in_window(preview_time, window).max(0.0).min(1.0);
+                    // Independent of the zoom level, so day/night coloring stays tied to the
+                    // actual time of day, not the visible window.
+                    let percent_of_day =
+                        percent_in_window(preview_time, (Time::START_OF_DAY, end_of_day)).min(1.0);
 
                     // TODO Why is the rounding so hard? The white background is always rounded
                     // at both ends. The moving bar should always be rounded on the left, flat
@@ -379,7 +783,7 @@ impl TimePanel {
 
                     if percent != 0.0 {
                         batch.push(
-                            if percent < 0.25 || percent > 0.75 {
+                            if percent_of_day < 0.25 || percent_of_day > 0.75 {
                                 app.cs.night_time_slider
                             } else {
                                 app.cs.day_time_slider
@@ -388,30 +792,359 @@ impl TimePanel {
                         );
                     }
 
-                    Widget::draw_batch(ctx, batch)
+                    // Labels are rendered directly into this batch at the same x offset as their
+                    // tick mark, rather than laid out in a separate evenly-spaced row. Ticks
+                    // only line up evenly when the window happens to span a round number of
+                    // label steps (true for the default full-day window); once the player pans
+                    // or zooms to an arbitrary sub-range, evenly-spaced labels drift out from
+                    // under the tick marks they're supposed to name.
+                    for (tick, show_label) in ticks_for_window(window) {
+                        let x = percent_in_window(tick, window) * width;
+                        if x < 0.0 || x > width {
+                            continue;
+                        }
+                        batch.push(
+                            app.cs.section_bg,
+                            Polygon::rectangle(1.0, height).translate(x, 0.0),
+                        );
+                        if show_label {
+                            let label_batch =
+                                Text::from(Line(tick.ampm_tostring()).small_monospaced())
+                                    .render_to_batch(ctx.prerender);
+                            let label_width = label_batch.get_dims().width;
+                            let label_x = (x - label_width / 2.0).max(0.0).min(width - label_width);
+                            batch.append(label_batch.translate(label_x, height + 2.0));
+                        }
+                    }
+
+                    Widget::draw_batch(ctx, batch).named("time slider")
                 },
-                Widget::custom_row(vec![
-                    Line("00:00").small_monospaced().draw(ctx),
-                    Widget::draw_svg(ctx, "system/assets/speed/sunrise.svg"),
-                    Line("12:00").small_monospaced().draw(ctx),
-                    Widget::draw_svg(ctx, "system/assets/speed/sunset.svg"),
-                    Line("24:00").small_monospaced().draw(ctx),
-                ])
-                .evenly_spaced(),
             ]))
             .aligned(HorizontalAlignment::Left, VerticalAlignment::Top)
             .build(ctx),
         }
     }
 
-    pub fn event(&mut self, ctx: &mut EventCtx, app: &mut App) {
-        if self.time != app.primary.sim.time() {
-            *self = TimePanel::new(ctx, app);
+    pub fn event(
+        &mut self,
+        ctx: &mut EventCtx,
+        app: &mut App,
+        maybe_mode: Option<&GameplayMode>,
+    ) -> Option<Transition> {
+        if self.time != app.primary.sim.time() && !self.scrubbing && self.pan_anchor.is_none() {
+            let window = self.window;
+            *self = TimePanel::new_with_preview(ctx, app, app.primary.sim.time(), window);
         }
         self.panel.event(ctx);
+
+        let bar_rect = self.panel.rect_of("time slider").clone();
+        let cursor = ctx.canvas.get_cursor_in_screen_space();
+
+        // Scroll to zoom into/out of a sub-range of the day, centered on the cursor.
+        if let Some(pt) = cursor {
+            if bar_rect.contains(pt) {
+                if let Some(scroll) = ctx.input.get_mouse_scroll() {
+                    self.zoom(ctx, app, pt, &bar_rect, scroll);
+                }
+            }
+        }
+
+        // Right-click-drag to pan the visible window left/right.
+        if self.pan_anchor.is_none() && ctx.input.right_mouse_button_pressed() {
+            if let Some(pt) = cursor {
+                if bar_rect.contains(pt) {
+                    self.pan_anchor = Some((pt, self.window));
+                }
+            }
+        }
+        if let Some((anchor_pt, anchor_window)) = self.pan_anchor {
+            if let Some(pt) = cursor {
+                let window_duration = anchor_window.1 - anchor_window.0;
+                let shift = window_duration * (-(pt.x - anchor_pt.x) / bar_rect.width());
+                self.window = clamp_window(
+                    app.primary.sim.get_end_of_day(),
+                    (anchor_window.0 + shift, anchor_window.1 + shift),
+                );
+                let preview_time = app.primary.sim.time();
+                let window = self.window;
+                *self = TimePanel::new_with_preview(ctx, app, preview_time, window);
+                self.pan_anchor = Some((anchor_pt, anchor_window));
+            }
+            if ctx.input.right_mouse_button_released() {
+                self.pan_anchor = None;
+            }
+        }
+
+        // Click-to-seek and press-drag-release-to-seek on the day progress bar.
+        if !self.scrubbing && ctx.input.left_mouse_button_pressed() {
+            if let Some(pt) = cursor {
+                if bar_rect.contains(pt) {
+                    self.scrubbing = true;
+                }
+            }
+        }
+
+        if self.scrubbing {
+            if let Some(pt) = cursor {
+                let percent = ((pt.x - bar_rect.x1) / bar_rect.width()).max(0.0).min(1.0);
+                let window = self.window;
+                let target = window.0 + (window.1 - window.0) * percent;
+                *self = TimePanel::new_with_preview(ctx, app, target, window);
+                self.scrubbing = true;
+
+                if ctx.input.left_mouse_button_released() {
+                    self.scrubbing = false;
+                    return self.seek_to(ctx, app, maybe_mode, target);
+                }
+            } else if ctx.input.left_mouse_button_released() {
+                self.scrubbing = false;
+            }
+        }
+
+        None
+    }
+
+    // Zoom in/out, keeping the time under the cursor fixed in place.
+    fn zoom(
+        &mut self,
+        ctx: &mut EventCtx,
+        app: &App,
+        pt: ScreenPt,
+        bar_rect: &ScreenRectangle,
+        scroll: f64,
+    ) {
+        let percent = ((pt.x - bar_rect.x1) / bar_rect.width()).max(0.0).min(1.0);
+        let anchor_time = self.window.0 + (self.window.1 - self.window.0) * percent;
+        let factor = if scroll > 0.0 {
+            ZOOM_FACTOR
+        } else {
+            1.0 / ZOOM_FACTOR
+        };
+        // Don't let the visible window shrink below a minute, or the ruler stops being useful.
+        let scaled_duration = (self.window.1 - self.window.0) * factor;
+        let new_duration = if scaled_duration > Duration::minutes(1) {
+            scaled_duration
+        } else {
+            Duration::minutes(1)
+        };
+        let new_start = anchor_time - new_duration * percent;
+        let new_end = new_start + new_duration;
+        self.window = clamp_window(app.primary.sim.get_end_of_day(), (new_start, new_end));
+
+        let preview_time = app.primary.sim.time();
+        let window = self.window;
+        *self = TimePanel::new_with_preview(ctx, app, preview_time, window);
+    }
+
+    // The sim can only step forwards, so jumping to a time behind the present means resetting
+    // to midnight and replaying up to that point.
+    fn seek_to(
+        &mut self,
+        ctx: &mut EventCtx,
+        app: &mut App,
+        maybe_mode: Option<&GameplayMode>,
+        target: Time,
+    ) -> Option<Transition> {
+        if target >= app.primary.sim.time() {
+            return Some(Transition::Push(TimeWarpScreen::new(ctx, app, target, None)));
+        }
+        if let Some(mode) = maybe_mode {
+            Some(Transition::Multi(vec![
+                Transition::Replace(SandboxMode::new(ctx, app, mode.clone())),
+                Transition::Push(TimeWarpScreen::new(ctx, app, target, None)),
+            ]))
+        } else {
+            Some(Transition::Push(PopupMsg::new(
+                ctx,
+                "Error",
+                vec!["Sorry, you can't rewind time from this mode."],
+            )))
+        }
     }
 
     pub fn draw(&self, g: &mut GfxCtx) {
         self.panel.draw(g);
     }
 }
+
+// Linear ease from `start` towards `target`, `frames_elapsed` out of `total_frames` of the way
+// there. Never overshoots `target`, regardless of rounding.
+fn ease_multiplier(start: f64, target: f64, frames_elapsed: usize, total_frames: usize) -> f64 {
+    let slope = (target - start) / (total_frames as f64);
+    let next = start + (frames_elapsed as f64) * slope;
+    if slope >= 0.0 {
+        next.min(target)
+    } else {
+        next.max(target)
+    }
+}
+
+#[cfg(test)]
+mod ease_multiplier_tests {
+    use super::ease_multiplier;
+
+    #[test]
+    fn ramps_linearly_towards_target() {
+        assert_eq!(ease_multiplier(1.0, 2.0, 0, 10), 1.0);
+        assert_eq!(ease_multiplier(1.0, 2.0, 5, 10), 1.5);
+        assert_eq!(ease_multiplier(1.0, 2.0, 10, 10), 2.0);
+    }
+
+    #[test]
+    fn ramps_down_towards_a_lower_target() {
+        assert_eq!(ease_multiplier(2.0, 1.0, 0, 10), 2.0);
+        assert_eq!(ease_multiplier(2.0, 1.0, 5, 10), 1.5);
+        assert_eq!(ease_multiplier(2.0, 1.0, 10, 10), 1.0);
+    }
+
+    #[test]
+    fn never_overshoots_target_past_total_frames() {
+        // Calling with frames_elapsed beyond total_frames shouldn't overshoot in either direction.
+        assert_eq!(ease_multiplier(1.0, 2.0, 20, 10), 2.0);
+        assert_eq!(ease_multiplier(2.0, 1.0, 20, 10), 1.0);
+    }
+}
+
+// How far into the window (as a percentage, not clamped) does this time fall?
+fn percent_in_window(t: Time, window: (Time, Time)) -> f64 {
+    (t - window.0).inner_seconds() / (window.1 - window.0).inner_seconds()
+}
+
+// Keeps the window within [00:00, day_end] and never inverted.
+fn clamp_window(day_end: Time, window: (Time, Time)) -> (Time, Time) {
+    let day_start = Time::START_OF_DAY;
+    let duration = window.1 - window.0;
+    let mut start = window.0;
+    if start < day_start {
+        start = day_start;
+    }
+    let mut end = start + duration;
+    if end > day_end {
+        end = day_end;
+        start = end - duration;
+        if start < day_start {
+            start = day_start;
+        }
+    }
+    (start, end)
+}
+
+// Pick the finest tick spacing (1min, 10min, 30min, hour) that still keeps the ruler from being
+// too crowded for the current zoom level, falling back to 3 hours when even hourly ticks are too
+// many.
+fn pick_tick_step(window_duration: Duration) -> Duration {
+    for step in [
+        Duration::minutes(1),
+        Duration::minutes(10),
+        Duration::minutes(30),
+        Duration::hours(1),
+    ] {
+        if window_duration.inner_seconds() / step.inner_seconds() <= MAX_TICKS {
+            return step;
+        }
+    }
+    Duration::hours(3)
+}
+
+// Only a fraction of ticks get a text label, coarsened one notch above the tick spacing itself,
+// so labels never overlap even as finer ticks appear while zooming in.
+fn label_step_for(step: Duration) -> Duration {
+    if step <= Duration::minutes(1) {
+        Duration::minutes(10)
+    } else if step <= Duration::minutes(10) {
+        Duration::minutes(30)
+    } else if step <= Duration::minutes(30) {
+        Duration::hours(1)
+    } else {
+        Duration::hours(3)
+    }
+}
+
+// Returns (tick time, whether to draw a text label at this tick) for every tick visible in the
+// window, adapting tick density and label density to the zoom level.
+fn ticks_for_window(window: (Time, Time)) -> Vec<(Time, bool)> {
+    let step = pick_tick_step(window.1 - window.0);
+    let label_step = label_step_for(step);
+
+    let step_secs = step.inner_seconds();
+    let label_secs = label_step.inner_seconds();
+    let start_secs = (window.0 - Time::START_OF_DAY).inner_seconds();
+    let end_secs = (window.1 - Time::START_OF_DAY).inner_seconds();
+
+    let mut ticks = Vec::new();
+    let mut t_secs = (start_secs / step_secs).ceil() * step_secs;
+    while t_secs <= end_secs {
+        let nearest_label_secs = (t_secs / label_secs).round() * label_secs;
+        let show_label = (t_secs - nearest_label_secs).abs() < 0.5;
+        ticks.push((Time::START_OF_DAY + Duration::seconds(t_secs), show_label));
+        t_secs += step_secs;
+    }
+    ticks
+}
+
+#[cfg(test)]
+mod window_and_tick_tests {
+    use super::{clamp_window, label_step_for, pick_tick_step, ticks_for_window};
+    use geom::{Duration, Time};
+
+    #[test]
+    fn clamp_window_leaves_an_in_bounds_window_untouched() {
+        let day_end = Time::START_OF_DAY + Duration::hours(24);
+        let window = (
+            Time::START_OF_DAY + Duration::hours(8),
+            Time::START_OF_DAY + Duration::hours(10),
+        );
+        assert_eq!(clamp_window(day_end, window), window);
+    }
+
+    #[test]
+    fn clamp_window_shifts_a_window_starting_before_the_day() {
+        let day_end = Time::START_OF_DAY + Duration::hours(24);
+        let window = (
+            Time::START_OF_DAY - Duration::hours(1),
+            Time::START_OF_DAY + Duration::hours(1),
+        );
+        assert_eq!(
+            clamp_window(day_end, window),
+            (Time::START_OF_DAY, Time::START_OF_DAY + Duration::hours(2))
+        );
+    }
+
+    #[test]
+    fn clamp_window_shifts_a_window_ending_after_the_day() {
+        let day_end = Time::START_OF_DAY + Duration::hours(24);
+        let window = (
+            Time::START_OF_DAY + Duration::hours(23),
+            Time::START_OF_DAY + Duration::hours(25),
+        );
+        assert_eq!(
+            clamp_window(day_end, window),
+            (Time::START_OF_DAY + Duration::hours(22), day_end)
+        );
+    }
+
+    #[test]
+    fn pick_tick_step_picks_the_finest_step_under_max_ticks() {
+        assert_eq!(pick_tick_step(Duration::minutes(10)), Duration::minutes(1));
+        assert_eq!(pick_tick_step(Duration::minutes(13)), Duration::minutes(10));
+        assert_eq!(pick_tick_step(Duration::hours(3)), Duration::minutes(30));
+        assert_eq!(pick_tick_step(Duration::hours(24)), Duration::hours(3));
+    }
+
+    #[test]
+    fn label_step_for_coarsens_one_notch_above_the_tick_step() {
+        assert_eq!(label_step_for(Duration::minutes(1)), Duration::minutes(10));
+        assert_eq!(label_step_for(Duration::minutes(10)), Duration::minutes(30));
+        assert_eq!(label_step_for(Duration::minutes(30)), Duration::hours(1));
+        assert_eq!(label_step_for(Duration::hours(1)), Duration::hours(3));
+    }
+
+    #[test]
+    fn ticks_for_window_labels_only_the_coarser_ticks() {
+        let window = (Time::START_OF_DAY, Time::START_OF_DAY + Duration::hours(1));
+        let ticks = ticks_for_window(window);
+        let labeled: Vec<bool> = ticks.iter().map(|(_, show_label)| *show_label).collect();
+        assert_eq!(ticks.len(), 7);
+        assert_eq!(labeled, vec![true, false, false, true, false, false, true]);
+    }
+}